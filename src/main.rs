@@ -5,10 +5,33 @@ use aws_types::credentials::SharedCredentialsProvider;
 
 #[tokio::main]
 pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut builder = sso::SSOProvider::builder().auto_login(env_flag("SSO_AUTO_LOGIN"));
+
+    if let Ok(role_arn) = std::env::var("SSO_ASSUME_ROLE_ARN") {
+        builder = builder.assume_role(role_arn);
+
+        if let Ok(session_name) = std::env::var("SSO_ASSUME_ROLE_SESSION_NAME") {
+            builder = builder.assume_role_session_name(session_name);
+        }
+        if let Ok(external_id) = std::env::var("SSO_ASSUME_ROLE_EXTERNAL_ID") {
+            builder = builder.assume_role_external_id(external_id);
+        }
+        if let Ok(duration_seconds) = std::env::var("SSO_ASSUME_ROLE_DURATION_SECONDS") {
+            if let Ok(duration_seconds) = duration_seconds.parse() {
+                builder = builder.assume_role_duration_seconds(duration_seconds);
+            }
+        }
+    }
+
+    let provider = builder.build()?;
+
+    if env_flag("SSO_LOGIN_ONLY") {
+        provider.login().await?;
+        return Ok(());
+    }
+
     let aws_config = aws_types::config::Config::builder()
-        .credentials_provider(SharedCredentialsProvider::new(
-            sso::SSOProvider::chained().await,
-        ))
+        .credentials_provider(SharedCredentialsProvider::new(provider.chained().await))
         .region(
             aws_config::default_provider::region::default_provider()
                 .region()
@@ -23,3 +46,9 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'sta
 
     Ok(())
 }
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
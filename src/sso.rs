@@ -1,19 +1,32 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration as StdDuration;
 
 use aws_config::default_provider::credentials::default_provider;
 use aws_config::meta::credentials::{CredentialsProviderChain, LazyCachingCredentialsProvider};
-use aws_types::credentials::{Credentials, CredentialsError, ProvideCredentials};
+use aws_sdk_ssooidc::error::CreateTokenErrorKind;
+use aws_sdk_ssooidc::SdkError;
+use aws_types::credentials::{
+    Credentials, CredentialsError, ProvideCredentials, SharedCredentialsProvider,
+};
 use aws_types::os_shim_internal::{Env, Fs};
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha1::{Digest, Sha1};
 use tokio::sync::Mutex;
+use zeroize::{Zeroize, Zeroizing};
+
+/// How close to the real expiry we consider a token expired, so we refresh
+/// slightly ahead of time instead of racing the SSO service.
+const TOKEN_EXPIRY_SKEW: Duration = Duration::seconds(30);
 
 #[derive(Clone, Debug)]
 pub enum SSOProviderError {
     RequiredConfigMissing(String),
+    TokenFileMalformed(String),
 }
 
 impl std::fmt::Display for SSOProviderError {
@@ -22,6 +35,9 @@ impl std::fmt::Display for SSOProviderError {
             Self::RequiredConfigMissing(field) => {
                 write!(f, "SSOProviderError: Missing required config: {}", field)
             }
+            Self::TokenFileMalformed(reason) => {
+                write!(f, "SSOProviderError: Malformed SSO token cache file: {}", reason)
+            }
         }
     }
 }
@@ -49,6 +65,8 @@ struct SSOProviderState {
 #[derive(Clone, Default)]
 pub struct SSOProvider {
     state: Arc<Mutex<SSOProviderState>>,
+    auto_login: bool,
+    assume_role: Option<RoleChainConfig>,
 }
 
 impl SSOProvider {
@@ -56,14 +74,101 @@ impl SSOProvider {
         Default::default()
     }
 
-    pub async fn chained() -> LazyCachingCredentialsProvider {
+    pub fn builder() -> SSOProviderBuilder {
+        SSOProviderBuilder::default()
+    }
+
+    pub async fn chained(self) -> LazyCachingCredentialsProvider {
         LazyCachingCredentialsProvider::builder()
             .load(
-                CredentialsProviderChain::first_try("sso", Self::new())
+                CredentialsProviderChain::first_try("sso", self)
                     .or_else("default", default_provider().await),
             )
             .build()
     }
+
+    /// Runs the OAuth device-authorization grant against `sso-oidc`,
+    /// bootstrapping a token cache file without depending on `aws sso login`
+    /// being available. Prints the verification URI the user must open.
+    pub async fn login(&self) -> Result<(), CredentialsError> {
+        let mut state = self.state.lock().await;
+
+        if state.sso_config.is_none() {
+            state.sso_config = Some(load_sso_config().await?);
+        }
+
+        let sso_config = state.sso_config.as_ref().unwrap().clone();
+        let cache_key = sso_config.cache_key().to_owned();
+
+        state.cached_token = Some(device_authorization_login(&sso_config, &cache_key).await?);
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct SSOProviderBuilder {
+    auto_login: bool,
+    assume_role: Option<RoleChainConfig>,
+}
+
+impl SSOProviderBuilder {
+    /// When enabled, `SSOProvider` performs the device-authorization login
+    /// flow itself instead of returning `CredentialsNotLoaded` when the
+    /// token cache is empty or unrefreshable.
+    pub fn auto_login(mut self, enabled: bool) -> Self {
+        self.auto_login = enabled;
+        self
+    }
+
+    /// Chains an `sts:AssumeRole` call onto the SSO "landing" credentials,
+    /// so `SSOProvider` hands out the downstream role's temporary
+    /// credentials instead. Overrides any `role_arn` found in the profile.
+    pub fn assume_role(mut self, role_arn: impl Into<String>) -> Self {
+        self.assume_role.get_or_insert_with(RoleChainConfig::default).role_arn = role_arn.into();
+        self
+    }
+
+    pub fn assume_role_session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.assume_role
+            .get_or_insert_with(RoleChainConfig::default)
+            .session_name = Some(session_name.into());
+        self
+    }
+
+    pub fn assume_role_external_id(mut self, external_id: impl Into<String>) -> Self {
+        self.assume_role
+            .get_or_insert_with(RoleChainConfig::default)
+            .external_id = Some(external_id.into());
+        self
+    }
+
+    pub fn assume_role_duration_seconds(mut self, duration_seconds: i32) -> Self {
+        self.assume_role
+            .get_or_insert_with(RoleChainConfig::default)
+            .duration_seconds = Some(duration_seconds);
+        self
+    }
+
+    /// Fails if `assume_role_session_name`/`assume_role_external_id`/
+    /// `assume_role_duration_seconds` were called without `assume_role`,
+    /// since that would otherwise silently build a role-chaining config
+    /// with an empty ARN and only fail later with an opaque STS error.
+    pub fn build(self) -> Result<SSOProvider, SSOProviderError> {
+        if let Some(role_chain) = &self.assume_role {
+            if role_chain.role_arn.is_empty() {
+                return Err(SSOProviderError::RequiredConfigMissing(
+                    "assume_role".to_owned(),
+                ));
+            }
+        }
+
+        Ok(SSOProvider {
+            auto_login: self.auto_login,
+            assume_role: self.assume_role,
+            ..SSOProvider::new()
+        })
+    }
 }
 
 impl std::fmt::Debug for SSOProvider {
@@ -78,9 +183,13 @@ impl ProvideCredentials for SSOProvider {
         Self: 'a,
     {
         let inner_state = self.state.clone();
+        let auto_login = self.auto_login;
+        let assume_role = self.assume_role.clone();
 
         aws_types::credentials::future::ProvideCredentials::new(do_provider_credentials(
             inner_state,
+            auto_login,
+            assume_role,
         ))
     }
 }
@@ -88,22 +197,151 @@ impl ProvideCredentials for SSOProvider {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CachedSSOToken {
-    access_token: String,
+    #[serde(with = "zeroizing_string")]
+    access_token: Zeroizing<String>,
     expires_at: DateTime<Utc>,
     region: String,
     start_url: String,
+    #[serde(skip_serializing_if = "Option::is_none", with = "zeroizing_string_opt")]
+    refresh_token: Option<Zeroizing<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "zeroizing_string_opt")]
+    client_secret: Option<Zeroizing<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    registration_expires_at: Option<DateTime<Utc>>,
+}
+
+/// `serde(with)` helpers so the cached token's secret fields live in
+/// `Zeroizing` containers (overwritten on drop) while still round-tripping
+/// through the cache file's plain-string JSON layout.
+mod zeroizing_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use zeroize::Zeroizing;
+
+    pub fn serialize<S>(value: &Zeroizing<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Zeroizing<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Zeroizing::new(String::deserialize(deserializer)?))
+    }
+}
+
+mod zeroizing_string_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use zeroize::Zeroizing;
+
+    pub fn serialize<S>(value: &Option<Zeroizing<String>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(value.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Zeroizing<String>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<String>::deserialize(deserializer)?.map(Zeroizing::new))
+    }
 }
 
+impl CachedSSOToken {
+    /// Whether the token is close enough to its real expiry that we should
+    /// proactively refresh it rather than racing the SSO service.
+    fn needs_refresh(&self) -> bool {
+        self.expires_at <= Utc::now() + TOKEN_EXPIRY_SKEW
+    }
+
+    /// Whether the token is actually unusable: past its real expiry with no
+    /// skew applied. This is the check that decides whether a cached token
+    /// read from disk is still worth returning at all.
+    fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    fn can_refresh(&self) -> bool {
+        self.refresh_token.is_some()
+            && self
+                .registration_expires_at
+                .map(|expires_at| expires_at > Utc::now())
+                .unwrap_or(false)
+    }
+}
+
+const DEFAULT_SSO_REGISTRATION_SCOPE: &str = "sso:account:access";
+
 #[derive(Clone, Default, Debug)]
 struct SSOConfig {
     sso_account_id: String,
     sso_role_name: String,
     sso_region: String,
     sso_start_url: String,
+    sso_session: Option<String>,
+    sso_registration_scopes: Option<Vec<String>>,
+    role_arn: Option<String>,
+    role_session_name: Option<String>,
+    external_id: Option<String>,
+    duration_seconds: Option<i32>,
+}
+
+impl SSOConfig {
+    /// The key the CLI uses to name the token cache file: the `sso-session`
+    /// name when the profile uses the `[sso-session]` indirection, otherwise
+    /// the legacy start URL.
+    fn cache_key(&self) -> &str {
+        self.sso_session.as_deref().unwrap_or(&self.sso_start_url)
+    }
+
+    fn registration_scopes(&self) -> Vec<String> {
+        self.sso_registration_scopes
+            .clone()
+            .unwrap_or_else(|| vec![DEFAULT_SSO_REGISTRATION_SCOPE.to_owned()])
+    }
+
+    /// The profile's `role_arn`-style chaining config, if it asks the
+    /// provider to fan the SSO "landing" credentials out into a target
+    /// role via `sts:AssumeRole`.
+    fn role_chain(&self) -> Option<RoleChainConfig> {
+        self.role_arn.as_ref().map(|role_arn| RoleChainConfig {
+            role_arn: role_arn.clone(),
+            session_name: self.role_session_name.clone(),
+            external_id: self.external_id.clone(),
+            duration_seconds: self.duration_seconds,
+        })
+    }
+}
+
+fn parse_registration_scopes(raw: &str) -> Vec<String> {
+    raw.split(',').map(|scope| scope.trim().to_owned()).collect()
+}
+
+/// Configuration for chaining an `sts:AssumeRole` call onto the SSO
+/// "landing" credentials, set either explicitly via
+/// [`SSOProviderBuilder::assume_role`] or read from the profile's
+/// `role_arn` (plus `role_session_name`/`external_id`/`duration_seconds`).
+#[derive(Clone, Debug, Default)]
+struct RoleChainConfig {
+    role_arn: String,
+    session_name: Option<String>,
+    external_id: Option<String>,
+    duration_seconds: Option<i32>,
 }
 
 async fn do_provider_credentials(
     state: Arc<Mutex<SSOProviderState>>,
+    auto_login: bool,
+    assume_role: Option<RoleChainConfig>,
 ) -> Result<Credentials, CredentialsError> {
     let mut state = state.lock().await;
 
@@ -111,19 +349,29 @@ async fn do_provider_credentials(
         state.sso_config = Some(load_sso_config().await?);
     }
 
-    if let Some(token) = &state.cached_token {
-        if token.expires_at <= Utc::now() {
-            state.cached_token = None;
+    let cache_key = state.sso_config.as_ref().unwrap().cache_key().to_owned();
+
+    if let Some(token) = state.cached_token.take() {
+        if token.needs_refresh() {
+            state.cached_token =
+                refresh_cached_token(state.sso_config.as_ref().unwrap(), &cache_key, &token).await;
+        } else {
+            state.cached_token = Some(token);
         }
     }
 
     if state.cached_token.is_none() {
-        state.cached_token =
-            load_token_file(&state.sso_config.as_ref().unwrap().sso_start_url).await;
+        state.cached_token = load_token_file(&cache_key).await?;
     }
 
     if state.cached_token.is_none() {
-        return Err(CredentialsError::CredentialsNotLoaded);
+        if !auto_login {
+            return Err(CredentialsError::CredentialsNotLoaded);
+        }
+
+        state.cached_token = Some(
+            device_authorization_login(state.sso_config.as_ref().unwrap(), &cache_key).await?,
+        );
     }
 
     let config = aws_sdk_sso::Config::builder()
@@ -134,11 +382,11 @@ async fn do_provider_credentials(
 
     let client = aws_sdk_sso::Client::from_conf(config);
 
-    if let Some(role_credentials) = client
+    if let Some(mut role_credentials) = client
         .get_role_credentials()
         .account_id(&state.sso_config.as_ref().unwrap().sso_account_id)
         .role_name(&state.sso_config.as_ref().unwrap().sso_role_name)
-        .access_token(&state.cached_token.as_ref().unwrap().access_token)
+        .access_token(state.cached_token.as_ref().unwrap().access_token.as_str())
         .send()
         .await
         .map_err(|e| CredentialsError::ProviderError(Box::new(e)))?
@@ -146,35 +394,173 @@ async fn do_provider_credentials(
     {
         let expiration = Utc.timestamp(role_credentials.expiration, 0);
 
-        return Ok(Credentials::new(
-            role_credentials.access_key_id.ok_or_else(|| {
-                CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
-                    "access_key_id".to_owned(),
-                )))
-            })?,
-            role_credentials.secret_access_key.ok_or_else(|| {
-                CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
-                    "secret_access_key".to_owned(),
-                )))
-            })?,
-            Some(role_credentials.session_token.ok_or_else(|| {
-                CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
-                    "session_token".to_owned(),
-                )))
-            })?),
+        let access_key_id = role_credentials.access_key_id.take().ok_or_else(|| {
+            CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
+                "access_key_id".to_owned(),
+            )))
+        })?;
+        let mut secret_access_key = role_credentials.secret_access_key.take().ok_or_else(|| {
+            CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
+                "secret_access_key".to_owned(),
+            )))
+        })?;
+        let mut session_token = role_credentials.session_token.take().ok_or_else(|| {
+            CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
+                "session_token".to_owned(),
+            )))
+        })?;
+
+        let credentials = Credentials::new(
+            access_key_id,
+            secret_access_key.clone(),
+            Some(session_token.clone()),
             Some(expiration.into()),
             "sso",
-        ));
+        );
+
+        // `role_credentials` is about to drop; scrub our local copies of the
+        // secrets it held rather than leaving them in freed heap memory.
+        secret_access_key.zeroize();
+        session_token.zeroize();
+
+        return match assume_role.or_else(|| state.sso_config.as_ref().unwrap().role_chain()) {
+            Some(role_chain) => {
+                assume_chained_role(
+                    credentials,
+                    &state.sso_config.as_ref().unwrap().sso_region,
+                    &role_chain,
+                )
+                .await
+            }
+            None => Ok(credentials),
+        };
     }
 
     Err(CredentialsError::CredentialsNotLoaded)
 }
 
+/// Exchanges the SSO "landing" credentials for a downstream role's
+/// temporary credentials via `sts:AssumeRole`, so a single SSO session can
+/// fan out to many per-account roles.
+async fn assume_chained_role(
+    sso_credentials: Credentials,
+    sso_region: &str,
+    role_chain: &RoleChainConfig,
+) -> Result<Credentials, CredentialsError> {
+    let config = aws_sdk_sts::Config::builder()
+        .region(aws_sdk_sts::Region::new(Cow::Owned(sso_region.to_owned())))
+        .credentials_provider(SharedCredentialsProvider::new(sso_credentials))
+        .build();
+
+    let client = aws_sdk_sts::Client::from_conf(config);
+
+    let session_name = role_chain
+        .session_name
+        .clone()
+        .unwrap_or_else(|| "aws-sdk-rust-sso-credentials".to_owned());
+
+    let mut assumed_credentials = client
+        .assume_role()
+        .role_arn(&role_chain.role_arn)
+        .role_session_name(session_name)
+        .set_external_id(role_chain.external_id.clone())
+        .set_duration_seconds(role_chain.duration_seconds)
+        .send()
+        .await
+        .map_err(|e| CredentialsError::ProviderError(Box::new(e)))?
+        .credentials
+        .ok_or_else(|| missing_field("credentials"))?;
+
+    let expiration = assumed_credentials
+        .expiration
+        .take()
+        .and_then(|expiration| std::time::SystemTime::try_from(expiration).ok());
+
+    let access_key_id = assumed_credentials
+        .access_key_id
+        .take()
+        .ok_or_else(|| missing_field("access_key_id"))?;
+    let mut secret_access_key = assumed_credentials
+        .secret_access_key
+        .take()
+        .ok_or_else(|| missing_field("secret_access_key"))?;
+    let mut session_token = assumed_credentials
+        .session_token
+        .take()
+        .ok_or_else(|| missing_field("session_token"))?;
+
+    let credentials = Credentials::new(
+        access_key_id,
+        secret_access_key.clone(),
+        Some(session_token.clone()),
+        expiration,
+        "sso-assume-role",
+    );
+
+    // `assumed_credentials` is about to drop; scrub our local copies of the
+    // secrets it held rather than leaving them in freed heap memory.
+    secret_access_key.zeroize();
+    session_token.zeroize();
+
+    Ok(credentials)
+}
+
 async fn load_sso_config() -> Result<SSOConfig, CredentialsError> {
-    let fs = Fs::default();
-    let env = Env::default();
+    load_sso_config_from(&Fs::default(), &Env::default()).await
+}
+
+/// The CLI config file path: `$AWS_CONFIG_FILE` if set, otherwise
+/// `~/.aws/config`. Reads `HOME` through the same `Env` shim as everything
+/// else in this function, so it stays mockable in tests.
+fn config_file_path(env: &Env) -> PathBuf {
+    if let Ok(path) = env.get("AWS_CONFIG_FILE") {
+        return PathBuf::from(path);
+    }
 
-    let profile_set = aws_config::profile::load(&fs, &env)
+    let home = env.get("HOME").expect("Need to have a home dir");
+    let mut path = PathBuf::from(home);
+    path.push(".aws");
+    path.push("config");
+    path
+}
+
+/// Hand-scans the raw config file text for a `[sso-session NAME]` block and
+/// returns its key/value pairs, ignoring every other section. Returns `None`
+/// if the section isn't present or holds no key/value pairs.
+fn find_sso_session_section(contents: &str, session_name: &str) -> Option<HashMap<String, String>> {
+    let header = format!("[sso-session {}]", session_name);
+    let mut in_section = false;
+    let mut values = HashMap::new();
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_section = trimmed == header;
+            continue;
+        }
+
+        if !in_section || trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = trimmed.split_once('=') {
+            values.insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// Does the actual profile-file reading and parsing behind [`load_sso_config`],
+/// taking the filesystem/environment shims as parameters so tests can point
+/// them at an in-memory config instead of the real `~/.aws`.
+async fn load_sso_config_from(fs: &Fs, env: &Env) -> Result<SSOConfig, CredentialsError> {
+    let profile_set = aws_config::profile::load(fs, env)
         .await
         .map_err(|_| CredentialsError::CredentialsNotLoaded)?;
 
@@ -182,15 +568,62 @@ async fn load_sso_config() -> Result<SSOConfig, CredentialsError> {
         return Err(CredentialsError::CredentialsNotLoaded);
     }
 
+    let role_arn = profile_set.get("role_arn").map(str::to_owned);
+    let role_session_name = profile_set.get("role_session_name").map(str::to_owned);
+    let external_id = profile_set.get("external_id").map(str::to_owned);
+    let duration_seconds = profile_set
+        .get("duration_seconds")
+        .and_then(|value| value.parse().ok());
+
     if let Some(sso_account_id) = profile_set.get("sso_account_id") {
         if let Some(sso_role_name) = profile_set.get("sso_role_name") {
-            if let Some(sso_region) = profile_set.get("sso_region") {
+            if let Some(sso_session) = profile_set.get("sso_session") {
+                // `ProfileSet` only exposes the selected profile's flattened
+                // keys (the `.get()`/`.is_empty()` surface this crate relies
+                // on everywhere else); it has no accessor we can depend on
+                // across versions for `[sso-session NAME]` blocks, which live
+                // in their own namespace separate from `[profile NAME]`
+                // sections. Resolve that indirection by hand-scanning the
+                // raw config file instead, the same way the token-cache
+                // parser tolerates whatever the CLI actually wrote.
+                let config_contents = fs
+                    .read_to_string(config_file_path(env))
+                    .await
+                    .map_err(|_| CredentialsError::CredentialsNotLoaded)?;
+                let session_section = find_sso_session_section(&config_contents, sso_session)
+                    .ok_or(CredentialsError::CredentialsNotLoaded)?;
+
+                if let Some(sso_region) = session_section.get("sso_region") {
+                    if let Some(sso_start_url) = session_section.get("sso_start_url") {
+                        return Ok(SSOConfig {
+                            sso_account_id: sso_account_id.to_owned(),
+                            sso_role_name: sso_role_name.to_owned(),
+                            sso_region: sso_region.to_owned(),
+                            sso_start_url: sso_start_url.to_owned(),
+                            sso_session: Some(sso_session.to_owned()),
+                            sso_registration_scopes: session_section
+                                .get("sso_registration_scopes")
+                                .map(|raw| parse_registration_scopes(raw)),
+                            role_arn,
+                            role_session_name,
+                            external_id,
+                            duration_seconds,
+                        });
+                    }
+                }
+            } else if let Some(sso_region) = profile_set.get("sso_region") {
                 if let Some(sso_start_url) = profile_set.get("sso_start_url") {
                     return Ok(SSOConfig {
                         sso_account_id: sso_account_id.to_owned(),
                         sso_role_name: sso_role_name.to_owned(),
                         sso_region: sso_region.to_owned(),
                         sso_start_url: sso_start_url.to_owned(),
+                        sso_session: None,
+                        sso_registration_scopes: None,
+                        role_arn,
+                        role_session_name,
+                        external_id,
+                        duration_seconds,
                     });
                 }
             }
@@ -200,29 +633,288 @@ async fn load_sso_config() -> Result<SSOConfig, CredentialsError> {
     Err(CredentialsError::CredentialsNotLoaded)
 }
 
-async fn load_token_file(start_url: &str) -> Option<CachedSSOToken> {
-    let mut filename = default_cache_location();
+/// Loads and parses the token cache file, tolerating unknown/reordered
+/// fields the way the AWS CLI's own cache writer does. Returns `Ok(None)`
+/// when the file is absent, empty, or holds an expired token, and `Err`
+/// only when the file exists but cannot be made sense of.
+async fn load_token_file(cache_key: &str) -> Result<Option<CachedSSOToken>, CredentialsError> {
+    let filename = token_cache_path(cache_key);
 
-    filename.push(get_cache_filename(start_url));
+    let contents = match tokio::fs::read_to_string(&filename).await {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
 
-    tokio::fs::read_to_string(&filename)
-        .await
+    if contents.trim().is_empty() {
+        return Ok(None);
+    }
+
+    parse_cached_token(&filename, &contents)
+}
+
+/// Walks the cache file's top-level JSON object picking out only the keys
+/// this crate understands, ignoring anything else the CLI may have added.
+/// A file that isn't a JSON object, or whose `expiresAt` can't be parsed,
+/// is reported as [`SSOProviderError::TokenFileMalformed`] rather than
+/// silently treated as "no token".
+fn parse_cached_token(
+    path: &Path,
+    contents: &str,
+) -> Result<Option<CachedSSOToken>, CredentialsError> {
+    let value: Value =
+        serde_json::from_str(contents).map_err(|e| malformed_token_file(path, e))?;
+
+    let object = value
+        .as_object()
+        .ok_or_else(|| malformed_token_file(path, "expected a JSON object"))?;
+
+    let access_token = match object.get("accessToken").and_then(Value::as_str) {
+        Some(access_token) if !access_token.is_empty() => access_token.to_owned(),
+        _ => return Ok(None),
+    };
+
+    let expires_at = match object.get("expiresAt").and_then(Value::as_str) {
+        Some(raw) => parse_token_timestamp(raw)
+            .ok_or_else(|| malformed_token_file(path, format!("invalid expiresAt {:?}", raw)))?,
+        None => return Ok(None),
+    };
+
+    let token = CachedSSOToken {
+        access_token: Zeroizing::new(access_token),
+        expires_at,
+        region: object
+            .get("region")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+        start_url: object
+            .get("startUrl")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+        refresh_token: object
+            .get("refreshToken")
+            .and_then(Value::as_str)
+            .map(|s| Zeroizing::new(s.to_owned())),
+        client_id: object
+            .get("clientId")
+            .and_then(Value::as_str)
+            .map(str::to_owned),
+        client_secret: object
+            .get("clientSecret")
+            .and_then(Value::as_str)
+            .map(|s| Zeroizing::new(s.to_owned())),
+        registration_expires_at: object
+            .get("registrationExpiresAt")
+            .and_then(Value::as_str)
+            .and_then(parse_token_timestamp),
+    };
+
+    if token.is_expired() {
+        return Ok(None);
+    }
+
+    Ok(Some(token))
+}
+
+/// Accepts both RFC 3339 and the AWS CLI's `%Y-%m-%dT%H:%M:%SZ` form, since
+/// different CLI versions have written both.
+fn parse_token_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
         .ok()
-        .and_then(|contents| serde_json::from_str::<CachedSSOToken>(&contents).ok())
-        .and_then(|cached_token| {
-            if cached_token.access_token.is_empty() {
-                None
-            } else {
-                Some(cached_token)
-            }
-        })
-        .and_then(|cached_token| {
-            if cached_token.expires_at <= Utc::now() {
-                None
-            } else {
-                Some(cached_token)
+        .or_else(|| Utc.datetime_from_str(raw, "%Y-%m-%dT%H:%M:%SZ").ok())
+}
+
+fn malformed_token_file(path: &Path, reason: impl std::fmt::Display) -> CredentialsError {
+    CredentialsError::Unhandled(Box::new(SSOProviderError::TokenFileMalformed(format!(
+        "{}: {}",
+        path.display(),
+        reason
+    ))))
+}
+
+/// Attempts to exchange a refresh token for a new access token via
+/// `sso-oidc:CreateToken`, persisting the result back to the cache file on
+/// success. Returns `None` if there is nothing to refresh with or the
+/// refresh itself fails; the caller then re-reads the cache file and, if
+/// that yields no token either, logs in (with `auto_login`) or returns
+/// `CredentialsNotLoaded`.
+async fn refresh_cached_token(
+    sso_config: &SSOConfig,
+    cache_key: &str,
+    token: &CachedSSOToken,
+) -> Option<CachedSSOToken> {
+    if !token.can_refresh() {
+        return None;
+    }
+
+    let config = aws_sdk_ssooidc::Config::builder()
+        .region(aws_sdk_ssooidc::Region::new(Cow::Owned(
+            sso_config.sso_region.to_owned(),
+        )))
+        .build();
+
+    let client = aws_sdk_ssooidc::Client::from_conf(config);
+
+    let output = client
+        .create_token()
+        .grant_type("refresh_token")
+        .client_id(token.client_id.clone()?)
+        .client_secret(token.client_secret.as_deref()?.clone())
+        .refresh_token(token.refresh_token.as_deref()?.clone())
+        .send()
+        .await
+        .ok()?;
+
+    let new_token = CachedSSOToken {
+        access_token: Zeroizing::new(output.access_token?),
+        expires_at: Utc::now() + Duration::seconds(output.expires_in.into()),
+        region: token.region.clone(),
+        start_url: token.start_url.clone(),
+        refresh_token: output
+            .refresh_token
+            .map(Zeroizing::new)
+            .or_else(|| token.refresh_token.clone()),
+        client_id: token.client_id.clone(),
+        client_secret: token.client_secret.clone(),
+        registration_expires_at: token.registration_expires_at,
+    };
+
+    let filename = token_cache_path(cache_key);
+
+    if let Err(err) = write_token_file(&filename, &new_token).await {
+        eprintln!("Failed to persist refreshed SSO token to {:?}: {}", filename, err);
+    }
+
+    Some(new_token)
+}
+
+async fn write_token_file(path: &Path, token: &CachedSSOToken) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let contents = serde_json::to_string_pretty(token)?;
+    let tmp_path = path.with_extension("json.tmp");
+
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+
+    Ok(())
+}
+
+/// Performs the OAuth device-authorization grant against `sso-oidc`:
+/// registers a public client, starts device authorization, prints the
+/// verification URI for the user to open, then polls `CreateToken` until
+/// they complete the flow in their browser. On success the token (plus the
+/// registration fields the refresh path needs) is persisted to the same
+/// cache file `load_token_file` reads.
+async fn device_authorization_login(
+    sso_config: &SSOConfig,
+    cache_key: &str,
+) -> Result<CachedSSOToken, CredentialsError> {
+    let config = aws_sdk_ssooidc::Config::builder()
+        .region(aws_sdk_ssooidc::Region::new(Cow::Owned(
+            sso_config.sso_region.to_owned(),
+        )))
+        .build();
+
+    let client = aws_sdk_ssooidc::Client::from_conf(config);
+
+    let registration = client
+        .register_client()
+        .client_name("aws-sdk-rust-sso-credentials")
+        .client_type("public")
+        .set_scopes(Some(sso_config.registration_scopes()))
+        .send()
+        .await
+        .map_err(|e| CredentialsError::ProviderError(Box::new(e)))?;
+
+    let client_id = registration
+        .client_id
+        .ok_or_else(|| missing_field("clientId"))?;
+    let client_secret = registration
+        .client_secret
+        .ok_or_else(|| missing_field("clientSecret"))?;
+    let registration_expires_at = Utc.timestamp(registration.client_secret_expires_at, 0);
+
+    let device_authorization = client
+        .start_device_authorization()
+        .client_id(&client_id)
+        .client_secret(&client_secret)
+        .start_url(&sso_config.sso_start_url)
+        .send()
+        .await
+        .map_err(|e| CredentialsError::ProviderError(Box::new(e)))?;
+
+    let device_code = device_authorization
+        .device_code
+        .ok_or_else(|| missing_field("deviceCode"))?;
+    let verification_uri_complete = device_authorization
+        .verification_uri_complete
+        .ok_or_else(|| missing_field("verificationUriComplete"))?;
+    let mut interval =
+        StdDuration::from_secs(device_authorization.interval.max(1) as u64);
+
+    println!(
+        "To authorize this device, visit: {}",
+        verification_uri_complete
+    );
+
+    loop {
+        match client
+            .create_token()
+            .grant_type("urn:ietf:params:oauth:grant-type:device_code")
+            .client_id(&client_id)
+            .client_secret(&client_secret)
+            .device_code(&device_code)
+            .send()
+            .await
+        {
+            Ok(output) => {
+                let new_token = CachedSSOToken {
+                    access_token: Zeroizing::new(
+                        output
+                            .access_token
+                            .ok_or_else(|| missing_field("accessToken"))?,
+                    ),
+                    expires_at: Utc::now() + Duration::seconds(output.expires_in.into()),
+                    region: sso_config.sso_region.clone(),
+                    start_url: sso_config.sso_start_url.clone(),
+                    refresh_token: output.refresh_token.map(Zeroizing::new),
+                    client_id: Some(client_id),
+                    client_secret: Some(Zeroizing::new(client_secret)),
+                    registration_expires_at: Some(registration_expires_at),
+                };
+
+                let filename = token_cache_path(cache_key);
+
+                if let Err(err) = write_token_file(&filename, &new_token).await {
+                    eprintln!("Failed to persist SSO token to {:?}: {}", filename, err);
+                }
+
+                return Ok(new_token);
             }
-        })
+            Err(SdkError::ServiceError { err, .. }) => match err.kind {
+                CreateTokenErrorKind::AuthorizationPendingException(_) => {
+                    tokio::time::sleep(interval).await;
+                }
+                CreateTokenErrorKind::SlowDownException(_) => {
+                    interval += StdDuration::from_secs(5);
+                    tokio::time::sleep(interval).await;
+                }
+                _ => return Err(CredentialsError::ProviderError(Box::new(err))),
+            },
+            Err(e) => return Err(CredentialsError::ProviderError(Box::new(e))),
+        }
+    }
+}
+
+fn missing_field(field: &str) -> CredentialsError {
+    CredentialsError::Unhandled(Box::new(SSOProviderError::RequiredConfigMissing(
+        field.to_owned(),
+    )))
 }
 
 fn default_cache_location() -> PathBuf {
@@ -235,6 +927,170 @@ fn default_cache_location() -> PathBuf {
     .collect()
 }
 
-fn get_cache_filename(start_url: &str) -> String {
-    hex::encode(Sha1::digest(start_url.as_bytes())) + ".json"
+fn token_cache_path(cache_key: &str) -> PathBuf {
+    let mut path = default_cache_location();
+    path.push(get_cache_filename(cache_key));
+    path
+}
+
+/// The CLI names token cache files by the SHA-1 of either the `sso-session`
+/// name (new-style profiles) or the legacy `sso_start_url`.
+fn get_cache_filename(cache_key: &str) -> String {
+    hex::encode(Sha1::digest(cache_key.as_bytes())) + ".json"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn load_sso_config_resolves_sso_session_indirection() {
+        let fs = Fs::from_slice(&[(
+            "/home/sso-test/.aws/config",
+            "[profile work]\n\
+             sso_session = my-session\n\
+             sso_account_id = 123456789012\n\
+             sso_role_name = AdministratorAccess\n\
+             \n\
+             [sso-session my-session]\n\
+             sso_region = us-east-1\n\
+             sso_start_url = https://example.awsapps.com/start\n",
+        )]);
+        let env = Env::from_slice(&[
+            ("HOME", "/home/sso-test"),
+            ("AWS_PROFILE", "work"),
+        ]);
+
+        let config = load_sso_config_from(&fs, &env).await.unwrap();
+
+        assert_eq!(config.sso_session.as_deref(), Some("my-session"));
+        assert_eq!(config.sso_region, "us-east-1");
+        assert_eq!(config.sso_start_url, "https://example.awsapps.com/start");
+        assert_eq!(config.cache_key(), "my-session");
+    }
+
+    #[test]
+    fn find_sso_session_section_extracts_matching_block_only() {
+        let contents = "[profile work]\n\
+             sso_session = my-session\n\
+             \n\
+             [sso-session my-session]\n\
+             sso_region = us-east-1\n\
+             sso_start_url = https://example.awsapps.com/start\n\
+             \n\
+             [sso-session other-session]\n\
+             sso_region = eu-west-1\n";
+
+        let section = find_sso_session_section(contents, "my-session").unwrap();
+
+        assert_eq!(section.get("sso_region").map(String::as_str), Some("us-east-1"));
+        assert_eq!(
+            section.get("sso_start_url").map(String::as_str),
+            Some("https://example.awsapps.com/start")
+        );
+    }
+
+    #[test]
+    fn find_sso_session_section_returns_none_when_absent() {
+        let contents = "[profile work]\nsso_region = us-east-1\n";
+
+        assert!(find_sso_session_section(contents, "my-session").is_none());
+    }
+
+    #[test]
+    fn cache_key_prefers_sso_session_over_start_url() {
+        let config = SSOConfig {
+            sso_start_url: "https://example.awsapps.com/start".to_owned(),
+            sso_session: Some("my-session".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(config.cache_key(), "my-session");
+    }
+
+    #[test]
+    fn cache_key_falls_back_to_start_url() {
+        let config = SSOConfig {
+            sso_start_url: "https://example.awsapps.com/start".to_owned(),
+            sso_session: None,
+            ..Default::default()
+        };
+
+        assert_eq!(config.cache_key(), "https://example.awsapps.com/start");
+    }
+
+    #[test]
+    fn parse_registration_scopes_splits_and_trims() {
+        assert_eq!(
+            parse_registration_scopes("sso:account:access, codecatalyst:read_write"),
+            vec!["sso:account:access".to_owned(), "codecatalyst:read_write".to_owned()],
+        );
+    }
+
+    #[test]
+    fn parse_token_timestamp_accepts_rfc3339() {
+        let parsed = parse_token_timestamp("2026-07-28T12:00:00+00:00").unwrap();
+        assert_eq!(parsed, Utc.ymd(2026, 7, 28).and_hms(12, 0, 0));
+    }
+
+    #[test]
+    fn parse_token_timestamp_accepts_cli_format() {
+        let parsed = parse_token_timestamp("2026-07-28T12:00:00Z").unwrap();
+        assert_eq!(parsed, Utc.ymd(2026, 7, 28).and_hms(12, 0, 0));
+    }
+
+    #[test]
+    fn parse_token_timestamp_rejects_garbage() {
+        assert!(parse_token_timestamp("not-a-date").is_none());
+    }
+
+    #[test]
+    fn parse_cached_token_rejects_malformed_json() {
+        let path = Path::new("/tmp/does-not-matter.json");
+        let err = parse_cached_token(path, "not json").unwrap_err();
+        assert!(matches!(
+            err,
+            CredentialsError::Unhandled(_)
+        ));
+    }
+
+    #[test]
+    fn parse_cached_token_treats_missing_access_token_as_absent() {
+        let path = Path::new("/tmp/does-not-matter.json");
+        let token =
+            parse_cached_token(path, r#"{"expiresAt": "2099-01-01T00:00:00Z"}"#).unwrap();
+        assert!(token.is_none());
+    }
+
+    #[test]
+    fn parse_cached_token_ignores_unknown_fields() {
+        let path = Path::new("/tmp/does-not-matter.json");
+        let token = parse_cached_token(
+            path,
+            r#"{
+                "accessToken": "token",
+                "expiresAt": "2099-01-01T00:00:00Z",
+                "region": "us-east-1",
+                "startUrl": "https://example.awsapps.com/start",
+                "someFutureCliField": "ignored"
+            }"#,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(token.access_token.as_str(), "token");
+        assert_eq!(token.region, "us-east-1");
+    }
+
+    #[test]
+    fn parse_cached_token_treats_expired_token_as_absent() {
+        let path = Path::new("/tmp/does-not-matter.json");
+        let token = parse_cached_token(
+            path,
+            r#"{"accessToken": "token", "expiresAt": "2000-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+
+        assert!(token.is_none());
+    }
 }